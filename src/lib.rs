@@ -1,28 +1,93 @@
 //! A priority queue implemented with a ternary heap.
-//! 
-
-
+//!
+
+use std::cmp::Ordering;
+use std::ops::{Deref, DerefMut};
+
+/// A priority queue implemented with a ternary heap, ordered by a
+/// caller-supplied comparator `F`.
+///
+/// [`TernaryHeap<T>`] is a thin specialization of this type with a
+/// plain function-pointer comparator; its default constructors supply
+/// `Ord::cmp`, recovering the usual `T: Ord` max-heap, and
+/// [`TernaryHeap::new_min`] supplies the reverse for a min-heap. Use
+/// this type directly when you need an ordering that isn't `T`'s
+/// natural one or its reverse.
 #[derive(Debug, Clone)]
-pub struct TernaryHeap<T> {
+pub struct TernaryHeapBy<T, F> {
     data: Vec<T>,
+    cmp: F,
 }
 
-impl <T: Ord> TernaryHeap<T> {
-    
-    /// Creates a new `TernaryHeap` as a max-heap.
-    pub fn new() -> Self {
-        TernaryHeap{ data: vec![] }
+impl <T, F: Fn(&T, &T) -> Ordering> TernaryHeapBy<T, F> {
+
+    /// Creates a new, empty `TernaryHeapBy` ordered by `cmp`.
+    pub fn new_by(cmp: F) -> Self {
+        TernaryHeapBy{ data: vec![], cmp }
     }
 
-    /// Creates a new `TernaryHeap` with a specified capacity.
-    /// This preallocates enough space for `capacity` elements,
-    /// so the internal `Vec` doesn't need to reallocate until 
+    /// Creates a new `TernaryHeapBy` with a specified capacity, ordered
+    /// by `cmp`. This preallocates enough space for `capacity`
+    /// elements, so the internal `Vec` doesn't need to reallocate until
     /// the heap contains that many values.
-    pub fn with_capacity(capacity: usize) -> Self {
-        TernaryHeap{ data: Vec::with_capacity(capacity) }
+    pub fn with_capacity_by(capacity: usize, cmp: F) -> Self {
+        TernaryHeapBy{ data: Vec::with_capacity(capacity), cmp }
+    }
+
+    /// Returns the number of elements the heap can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more elements, as
+    /// `Vec::reserve`.
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+    }
+
+    /// Reserves capacity for exactly `additional` more elements, as
+    /// `Vec::reserve_exact`.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.data.reserve_exact(additional);
+    }
+
+    /// Discards excess capacity, as `Vec::shrink_to_fit`.
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+    }
+
+    /// Removes every element from the heap.
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Consumes the heap and returns its elements in arbitrary
+    /// (internal) order, distinct from the ascending order of
+    /// `into_sorted_vec`.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// Returns the greatest value (under `cmp`) in the heap, or `None`
+    /// if it is empty, without removing it.
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
     }
 
-    /// Removes the greatest value from the heap and returns it, 
+    /// Returns a guard granting mutable access to the greatest value in
+    /// the heap, or `None` if it is empty. The heap property may be
+    /// temporarily violated while the guard is alive; it is restored
+    /// automatically when the guard is dropped.
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, F>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMut { heap: self, sift: false })
+        }
+    }
+
+    /// Removes the greatest value from the heap and returns it,
     /// or `None` if the heap is empty.
     pub fn pop(&mut self) -> Option<T> {
         if !self.is_empty() {
@@ -34,6 +99,19 @@ impl <T: Ord> TernaryHeap<T> {
         self.data.pop()
     }
 
+    /// Consumes the `TernaryHeapBy` and returns its elements as a
+    /// vector sorted ascending by `cmp`, reusing the backing storage in
+    /// place instead of allocating a new one.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut end = self.len();
+        while end > 1 {
+            end -= 1;
+            self.data.swap(0, end);
+            self.sink_until(0, end);
+        }
+        self.data
+    }
+
     /// Adds a value to the heap.
     pub fn push(&mut self, item: T) {
         let old_len = self.len();
@@ -51,11 +129,42 @@ impl <T: Ord> TernaryHeap<T> {
         self.data.is_empty()
     }
 
+    /// Returns an iterator over the heap's elements in arbitrary
+    /// (internal) order.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.data.iter()
+    }
+
+    /// Moves all elements out of `other` into `self`, leaving `other`
+    /// empty. Pushes elements one at a time if `other` is small
+    /// relative to `self` (`O(m log n)`), or appends and rebuilds the
+    /// whole heap bottom-up otherwise (`O(n + m)`).
+    pub fn append(&mut self, other: &mut TernaryHeapBy<T, F>) {
+        if other.len() * 4 < self.len() {
+            while let Some(item) = other.pop() {
+                self.push(item);
+            }
+        } else {
+            self.data.append(&mut other.data);
+            self.rebuild();
+        }
+    }
+
+    fn rebuild(&mut self) {
+        if self.data.is_empty() {
+            return;
+        }
+        let last_parent = Self::parent(self.len() - 1);
+        for parent in (0..=last_parent).rev() {
+            self.sink(parent);
+        }
+    }
+
     fn swim(&mut self, pos: usize) {
         let mut pos = pos;
         while pos > 0 {
             let parent = Self::parent(pos);
-            if self.data[pos] <= self.data[parent] {
+            if (self.cmp)(&self.data[pos], &self.data[parent]) != Ordering::Greater {
                 return;
             }
             self.data.swap(pos, parent);
@@ -66,7 +175,7 @@ impl <T: Ord> TernaryHeap<T> {
     fn sink_until(&mut self, pos: usize, end: usize) {
         let mut pos = pos;
         while let Some(child) = self.best_child(pos, end) {
-            if self.data[pos] >= self.data[child] {
+            if (self.cmp)(&self.data[pos], &self.data[child]) != Ordering::Less {
                 return;
             }
             self.data.swap(pos, child);
@@ -80,7 +189,7 @@ impl <T: Ord> TernaryHeap<T> {
 
     fn best_child(&self, parent: usize, end: usize) -> Option<usize> {
         match Self::children(parent, end) {
-            Some(vec) => vec.into_iter().max_by_key(|i| &self.data[*i]),
+            Some(vec) => vec.into_iter().max_by(|a, b| (self.cmp)(&self.data[*a], &self.data[*b])),
             None => None
         }
     }
@@ -102,25 +211,158 @@ impl <T: Ord> TernaryHeap<T> {
         let result = (first_child..last_child).collect();
         return Some(result);
     }
+
+    fn from_vec_by(vec: Vec<T>, cmp: F) -> Self {
+        let mut heap = TernaryHeapBy{ data: vec, cmp };
+        heap.rebuild();
+        heap
+    }
+}
+
+/// A guard granting mutable access to the greatest element of a
+/// [`TernaryHeapBy`], returned by [`TernaryHeapBy::peek_mut`].
+///
+/// The root is only re-sifted once, when the guard is dropped, so that
+/// a caller mutating the peeked value through [`DerefMut`] pays for a
+/// single resink rather than one per write.
+pub struct PeekMut<'a, T, F: Fn(&T, &T) -> Ordering> {
+    heap: &'a mut TernaryHeapBy<T, F>,
+    sift: bool,
+}
+
+impl<'a, T, F: Fn(&T, &T) -> Ordering> Drop for PeekMut<'a, T, F> {
+    fn drop(&mut self) {
+        if self.sift {
+            let len = self.heap.len();
+            self.heap.sink_until(0, len);
+        }
+    }
+}
+
+impl<'a, T, F: Fn(&T, &T) -> Ordering> Deref for PeekMut<'a, T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.heap.data[0]
+    }
+}
+
+impl<'a, T, F: Fn(&T, &T) -> Ordering> DerefMut for PeekMut<'a, T, F> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.sift = true;
+        &mut self.heap.data[0]
+    }
+}
+
+impl<'a, T, F: Fn(&T, &T) -> Ordering> PeekMut<'a, T, F> {
+    /// Removes the peeked value from the heap and returns it. The
+    /// guard's drop-time re-sift is skipped, since
+    /// [`TernaryHeapBy::pop`] already restores the heap property.
+    pub fn pop(mut this: PeekMut<'a, T, F>) -> T {
+        this.sift = false;
+        this.heap.pop().unwrap()
+    }
+}
+
+/// A priority queue implemented with a ternary heap over `T`'s natural
+/// ordering: a max-heap by default, or a min-heap via
+/// [`TernaryHeap::new_min`].
+///
+/// This is [`TernaryHeapBy`] specialized with a function-pointer
+/// comparator; `new`, `with_capacity`, `From<Vec<T>>` and `collect`
+/// supply `Ord::cmp`. Use [`TernaryHeapBy`] directly for a custom ordering, or
+/// [`TernaryHeap::new_min`] for a min-heap without wrapping every
+/// element in `core::cmp::Reverse`.
+pub type TernaryHeap<T> = TernaryHeapBy<T, fn(&T, &T) -> Ordering>;
+
+impl <T: Ord> TernaryHeap<T> {
+
+    /// Creates a new `TernaryHeap` as a max-heap.
+    pub fn new() -> Self {
+        Self::new_by(T::cmp)
+    }
+
+    /// Creates a new `TernaryHeap` with a specified capacity.
+    /// This preallocates enough space for `capacity` elements,
+    /// so the internal `Vec` doesn't need to reallocate until
+    /// the heap contains that many values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_by(capacity, T::cmp)
+    }
+
+    /// Creates a new `TernaryHeap` ordered so the least value (under
+    /// `Ord`) sorts to the top, i.e. a min-heap.
+    pub fn new_min() -> Self {
+        Self::new_by(|a, b| b.cmp(a))
+    }
+
+    /// Creates a new `TernaryHeap` ordered so the greatest value (under
+    /// `Ord`) sorts to the top, i.e. a max-heap. Equivalent to
+    /// [`TernaryHeap::new`].
+    pub fn new_max() -> Self {
+        Self::new_by(T::cmp)
+    }
 }
 
 impl<T: Ord> From<Vec<T>> for TernaryHeap<T> {
     fn from(vec: Vec<T>) -> Self {
-        let mut heap = TernaryHeap{ data: vec };
-        let last_parent = Self::parent(heap.len() - 1);
-        for parent in (0..=last_parent).rev() {
-            heap.sink(parent);
-        }
-        heap
+        Self::from_vec_by(vec, T::cmp)
     }
 }
 
-impl<T> From<TernaryHeap<T>> for Vec<T> {
-    fn from(heap: TernaryHeap<T>) -> Self {
+impl<T, F> From<TernaryHeapBy<T, F>> for Vec<T> {
+    fn from(heap: TernaryHeapBy<T, F>) -> Self {
         heap.data
     }
 }
 
+impl<T: Ord> FromIterator<T> for TernaryHeap<T> {
+    /// Collects the iterator into a `Vec` and heapifies it bottom-up in
+    /// `O(n)`, rather than inserting one element at a time.
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let vec: Vec<T> = iter.into_iter().collect();
+        Self::from_vec_by(vec, T::cmp)
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> Extend<T> for TernaryHeapBy<T, F> {
+    /// Pushes elements one at a time if there are few of them relative
+    /// to the heap's current size, or appends and re-heapifies from
+    /// scratch otherwise.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        let (lower, _) = iter.size_hint();
+        if lower * 4 < self.len() {
+            for item in iter {
+                self.push(item);
+            }
+        } else {
+            self.data.extend(iter);
+            self.rebuild();
+        }
+    }
+}
+
+impl<T, F> IntoIterator for TernaryHeapBy<T, F> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    /// Consumes the heap, yielding its elements in arbitrary (internal)
+    /// order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, T, F> IntoIterator for &'a TernaryHeapBy<T, F> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -152,7 +394,7 @@ mod tests {
 
         let mut binary = BinaryHeap::new();
         let mut trnary = TernaryHeap::new();
-        
+
         while !vec.is_empty() {
             if rand.gen() {
                 let value = vec.pop().unwrap();
@@ -170,6 +412,33 @@ mod tests {
         assert!(trnary.is_empty());
     }
 
+    #[test]
+    fn peek_returns_max_without_removing() {
+        let heap: TernaryHeap<_> = vec![3, 1, 4, 1, 5].into();
+        assert_eq!(heap.peek(), Some(&5));
+        assert_eq!(heap.len(), 5);
+    }
+
+    #[test]
+    fn peek_mut_resinks_on_drop() {
+        let mut heap: TernaryHeap<_> = vec![3, 1, 4, 1, 5].into();
+        {
+            let mut top = heap.peek_mut().unwrap();
+            *top = 0;
+        }
+        heap.verify_heap();
+        assert_eq!(heap.peek(), Some(&4));
+    }
+
+    #[test]
+    fn peek_mut_pop_removes_without_resinking() {
+        let mut heap: TernaryHeap<_> = vec![3, 1, 4, 1, 5].into();
+        let top = heap.peek_mut().unwrap();
+        assert_eq!(PeekMut::pop(top), 5);
+        heap.verify_heap();
+        assert_eq!(heap.len(), 4);
+    }
+
     #[test]
     fn single_test_from_vec() {
         specific_test_from_vec(10);
@@ -199,15 +468,152 @@ mod tests {
         }
     }
 
+    #[test]
+    fn single_test_into_sorted_vec() {
+        specific_test_into_sorted_vec(10);
+    }
+
+    #[test]
+    #[ignore]
+    fn many_test_into_sorted_vec() {
+        for i in 0..TEST_ITERATIONS {
+            specific_test_into_sorted_vec(i);
+        }
+    }
+
+    fn specific_test_into_sorted_vec(seed: u64) {
+        let mut rand = SmallRng::seed_from_u64(seed);
+        let mut vec: Vec<_> = (0..TEST_SIZE).collect();
+        vec.shuffle(&mut rand);
+
+        let mut expected = vec.clone();
+        expected.sort();
+
+        let heap: TernaryHeap<_> = vec.into();
+        assert_eq!(heap.into_sorted_vec(), expected);
+    }
+
+    #[test]
+    fn new_min_pops_ascending() {
+        let mut heap = TernaryHeap::new_min();
+        for value in [3, 1, 4, 1, 5, 9, 2, 6] {
+            heap.push(value);
+        }
+
+        let mut last = heap.pop();
+        while !heap.is_empty() {
+            let next = heap.pop();
+            assert!(last <= next);
+            last = next;
+        }
+    }
+
+    #[test]
+    fn new_by_supports_custom_ordering() {
+        let mut heap = TernaryHeapBy::new_by(|a: &&str, b: &&str| a.len().cmp(&b.len()));
+        for word in ["a", "ccc", "bb", "dddd"] {
+            heap.push(word);
+        }
+        assert_eq!(heap.pop(), Some("dddd"));
+        assert_eq!(heap.pop(), Some("ccc"));
+        assert_eq!(heap.pop(), Some("bb"));
+        assert_eq!(heap.pop(), Some("a"));
+    }
+
+    #[test]
+    fn iter_visits_every_element() {
+        let heap: TernaryHeap<_> = vec![3, 1, 4, 1, 5].into();
+        let mut seen: Vec<_> = heap.iter().copied().collect();
+        seen.sort();
+        assert_eq!(seen, vec![1, 1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn into_iter_yields_every_element() {
+        let heap: TernaryHeap<_> = vec![3, 1, 4, 1, 5].into();
+        let mut collected: Vec<_> = heap.into_iter().collect();
+        collected.sort();
+        assert_eq!(collected, vec![1, 1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn collect_builds_a_valid_heap() {
+        let heap: TernaryHeap<_> = (0..TEST_SIZE).collect();
+        heap.verify_heap();
+        assert_eq!(heap.len(), TEST_SIZE as usize);
+    }
+
+    #[test]
+    fn extend_restores_heap_property() {
+        let mut heap: TernaryHeap<_> = vec![5, 2, 8].into();
+        heap.extend(vec![1, 9, 3, 7]);
+        heap.verify_heap();
+        assert_eq!(heap.len(), 7);
+        assert_eq!(heap.peek(), Some(&9));
+    }
+
+    #[test]
+    fn append_merges_and_empties_other() {
+        let mut heap: TernaryHeap<_> = vec![5, 2, 8].into();
+        let mut other: TernaryHeap<_> = vec![1, 9, 3, 7].into();
+        heap.append(&mut other);
+
+        heap.verify_heap();
+        assert_eq!(heap.len(), 7);
+        assert_eq!(heap.peek(), Some(&9));
+        assert!(other.is_empty());
+    }
+
+    #[test]
+    fn append_with_much_larger_self_still_merges_all() {
+        let mut heap: TernaryHeap<_> = (0..TEST_SIZE).collect();
+        let mut other: TernaryHeap<_> = vec![TEST_SIZE, TEST_SIZE + 1].into();
+        heap.append(&mut other);
+
+        heap.verify_heap();
+        assert_eq!(heap.len(), TEST_SIZE as usize + 2);
+        assert_eq!(heap.peek(), Some(&(TEST_SIZE + 1)));
+        assert!(other.is_empty());
+    }
+
+    #[test]
+    fn with_capacity_reserves_up_front() {
+        let heap: TernaryHeap<i32> = TernaryHeap::with_capacity(42);
+        assert!(heap.capacity() >= 42);
+    }
+
+    #[test]
+    fn reserve_grows_capacity() {
+        let mut heap: TernaryHeap<_> = vec![1, 2, 3].into();
+        heap.reserve(100);
+        assert!(heap.capacity() >= 103);
+    }
+
+    #[test]
+    fn clear_empties_the_heap() {
+        let mut heap: TernaryHeap<_> = vec![1, 2, 3].into();
+        heap.clear();
+        assert!(heap.is_empty());
+        assert_eq!(heap.peek(), None);
+    }
+
+    #[test]
+    fn into_vec_preserves_elements_in_any_order() {
+        let heap: TernaryHeap<_> = vec![3, 1, 4, 1, 5].into();
+        let mut vec = heap.into_vec();
+        vec.sort();
+        assert_eq!(vec, vec![1, 1, 3, 4, 5]);
+    }
+
 
-    impl<T: Ord + std::fmt::Debug> TernaryHeap<T> {
+    impl<T: Ord + std::fmt::Debug, F: Fn(&T, &T) -> Ordering> TernaryHeapBy<T, F> {
         fn verify_heap(&self) {
             for (i, val) in self.data.iter().enumerate() {
                 if let Some(children) = Self::children(i, self.len()) {
                     for child_index in children {
                         assert!(
-                            val >= &self.data[child_index], 
-                            "Heap condition broken between indices {} (value: {:?}) and {} (value: {:?})\n{:?})", 
+                            val >= &self.data[child_index],
+                            "Heap condition broken between indices {} (value: {:?}) and {} (value: {:?})\n{:?})",
                             child_index, &self.data[child_index], i, val, self.data
                         );
                     }